@@ -0,0 +1,82 @@
+//! Debug-line overlay: light paths, camera frustum and per-entity bounding spheres.
+use amethyst::{
+    core::{
+        ecs::{Join, Read, ReadStorage, System, Write},
+        math::{Point3, UnitQuaternion, Vector3},
+        Transform,
+    },
+    renderer::{
+        camera::Camera,
+        debug_drawing::DebugLines,
+        light::Light,
+        palette::Srgba,
+        visibility::BoundingSphere,
+    },
+    utils::fps_counter::FpsCounter,
+};
+
+/// Draws the culling volumes, light positions and camera forward lines into the
+/// immediate-mode `DebugLines` resource every frame.
+pub struct DebugDrawSystem;
+
+impl<'s> System<'s> for DebugDrawSystem {
+    type SystemData = (
+        Write<'s, DebugLines>,
+        ReadStorage<'s, Transform>,
+        ReadStorage<'s, BoundingSphere>,
+        ReadStorage<'s, Light>,
+        ReadStorage<'s, Camera>,
+    );
+
+    fn run(&mut self, (mut lines, transforms, spheres, lights, cameras): Self::SystemData) {
+        let volume_color = Srgba::new(0.2, 0.8, 0.2, 1.0);
+        let light_color = Srgba::new(1.0, 1.0, 0.4, 1.0);
+        let camera_color = Srgba::new(0.4, 0.6, 1.0, 1.0);
+
+        // Per-entity bounding spheres, drawn as three orthogonal circles since
+        // the immediate-mode resource has no sphere primitive.
+        let quarter_x = UnitQuaternion::from_axis_angle(
+            &Vector3::x_axis(),
+            std::f32::consts::FRAC_PI_2,
+        );
+        let quarter_y = UnitQuaternion::from_axis_angle(
+            &Vector3::y_axis(),
+            std::f32::consts::FRAC_PI_2,
+        );
+        for (transform, sphere) in (&transforms, &spheres).join() {
+            let center = transform.global_matrix().transform_point(&sphere.center);
+            lines.draw_circle(center, sphere.radius, 32, volume_color);
+            lines.draw_rotated_circle(center, sphere.radius, 32, quarter_x, volume_color);
+            lines.draw_rotated_circle(center, sphere.radius, 32, quarter_y, volume_color);
+        }
+
+        // A line from the origin to each light marks its animated path.
+        for (transform, _light) in (&transforms, &lights).join() {
+            let pos = transform.translation();
+            lines.draw_line(
+                Point3::origin(),
+                Point3::new(pos.x, pos.y, pos.z),
+                light_color,
+            );
+        }
+
+        // A line along each camera's forward direction stands in for the view frustum.
+        for (transform, _camera) in (&transforms, &cameras).join() {
+            let pos = transform.translation();
+            let origin = Point3::new(pos.x, pos.y, pos.z);
+            let forward = transform.global_matrix().transform_vector(&(-10.0 * amethyst::core::math::Vector3::z()));
+            lines.draw_line(origin, origin + forward, camera_color);
+        }
+    }
+}
+
+/// Logs the sampled frames-per-second at debug level every frame.
+pub struct FpsDisplaySystem;
+
+impl<'s> System<'s> for FpsDisplaySystem {
+    type SystemData = Read<'s, FpsCounter>;
+
+    fn run(&mut self, fps: Self::SystemData) {
+        log::debug!("fps: {:.1}", fps.sampled_fps());
+    }
+}
@@ -0,0 +1,240 @@
+//! Loads a glTF/GLB file and spawns one entity per node, preserving the scene graph.
+use amethyst::{
+    assets::AssetLoaderSystemData,
+    core::{
+        ecs::{Builder, World, WorldExt},
+        math::{Matrix3, Matrix4, Point3, Quaternion, Unit, UnitQuaternion, Vector3},
+        Transform,
+    },
+    renderer::{
+        mtl::{Material, MaterialDefaults},
+        palette::LinSrgba,
+        rendy::{
+            mesh::{MeshBuilder, Normal, Position, Tangent, TexCoord},
+            texture::palette::load_from_linear_rgba,
+        },
+        visibility::BoundingSphere,
+        Mesh, Texture,
+    },
+};
+
+/// A single glTF primitive ready to be handed to `AssetLoaderSystemData<Mesh>`.
+struct PrimitiveData {
+    positions: Vec<Position>,
+    normals: Vec<Normal>,
+    tangents: Vec<Tangent>,
+    tex_coords: Vec<TexCoord>,
+    indices: Vec<u32>,
+    base_color: LinSrgba,
+}
+
+/// Walk the default scene of `path` depth-first, composing each node's local TRS
+/// transform with its parent's world transform, and spawn an entity carrying
+/// `Transform` + `Mesh` + `Material` for every primitive found on a node.
+pub fn load_scene(world: &mut World, path: &str) {
+    let (document, buffers, _images) = match gltf::import(path) {
+        Ok(loaded) => loaded,
+        Err(err) => {
+            log::error!("Failed to load glTF scene `{}`: {}", path, err);
+            return;
+        }
+    };
+
+    let scene = match document.default_scene().or_else(|| document.scenes().next()) {
+        Some(scene) => scene,
+        None => {
+            log::warn!("glTF file `{}` contains no scenes", path);
+            return;
+        }
+    };
+
+    for node in scene.nodes() {
+        spawn_node(world, &node, &buffers, Matrix4::identity());
+    }
+}
+
+/// Recursively spawn `node` and its children, threading the accumulated world
+/// matrix down the tree.
+fn spawn_node(
+    world: &mut World,
+    node: &gltf::Node,
+    buffers: &[gltf::buffer::Data],
+    parent_world: Matrix4<f32>,
+) {
+    let local = local_matrix(node);
+    let world_matrix = parent_world * local;
+
+    if let Some(mesh) = node.mesh() {
+        for primitive in mesh.primitives() {
+            let data = read_primitive(&primitive, buffers);
+            spawn_primitive(world, &world_matrix, data);
+        }
+    }
+
+    for child in node.children() {
+        spawn_node(world, &child, buffers, world_matrix);
+    }
+}
+
+/// Build the local TRS matrix for a node from its translation, rotation
+/// quaternion and scale.
+fn local_matrix(node: &gltf::Node) -> Matrix4<f32> {
+    let (t, r, s) = node.transform().decomposed();
+    let translation = Vector3::new(t[0], t[1], t[2]);
+    let rotation = Unit::new_normalize(Quaternion::new(r[3], r[0], r[1], r[2]));
+    let scale = Vector3::new(s[0], s[1], s[2]);
+
+    Matrix4::new_translation(&translation)
+        * rotation.to_homogeneous()
+        * Matrix4::new_nonuniform_scaling(&scale)
+}
+
+/// Pull interleaved vertex attributes and indices out of a primitive's buffers.
+fn read_primitive(primitive: &gltf::Primitive, buffers: &[gltf::buffer::Data]) -> PrimitiveData {
+    let reader = primitive.reader(|buffer| Some(&buffers[buffer.index()]));
+
+    let positions = reader
+        .read_positions()
+        .map(|iter| iter.map(Position).collect())
+        .unwrap_or_default();
+    let normals = reader
+        .read_normals()
+        .map(|iter| iter.map(Normal).collect())
+        .unwrap_or_default();
+    let tangents = reader
+        .read_tangents()
+        .map(|iter| iter.map(|t| Tangent([t[0], t[1], t[2], t[3]])).collect())
+        .unwrap_or_default();
+    let tex_coords = reader
+        .read_tex_coords(0)
+        .map(|tc| tc.into_f32().map(TexCoord).collect())
+        .unwrap_or_default();
+    // Synthesize any attributes the authored mesh omitted so every buffer
+    // matches `positions.len()` — rendy asserts on mismatched lengths.
+    let count = positions.len();
+    let normals = pad(normals, count, Normal([0.0, 1.0, 0.0]));
+    let tangents = pad(tangents, count, Tangent([0.0, 0.0, 1.0, 1.0]));
+    let tex_coords = pad(tex_coords, count, TexCoord([0.0, 0.0]));
+
+    let indices = reader
+        .read_indices()
+        .map(|iter| iter.into_u32().collect())
+        .unwrap_or_default();
+
+    let base = primitive
+        .material()
+        .pbr_metallic_roughness()
+        .base_color_factor();
+    let base_color = LinSrgba::new(base[0], base[1], base[2], base[3]);
+
+    PrimitiveData {
+        positions,
+        normals,
+        tangents,
+        tex_coords,
+        indices,
+        base_color,
+    }
+}
+
+/// Replace an empty attribute buffer with `count` copies of `fill`; leave a
+/// populated buffer untouched.
+fn pad<T: Clone>(buffer: Vec<T>, count: usize, fill: T) -> Vec<T> {
+    if buffer.is_empty() {
+        vec![fill; count]
+    } else {
+        buffer
+    }
+}
+
+/// Decompose `m` into translation/rotation/scale and write them onto `transform`
+/// so the transform system rebuilds the matching `global_matrix` each frame.
+fn apply_world_transform(transform: &mut Transform, m: &Matrix4<f32>) {
+    let translation = Vector3::new(m[(0, 3)], m[(1, 3)], m[(2, 3)]);
+    let sx = m.column(0).xyz().norm();
+    let sy = m.column(1).xyz().norm();
+    let sz = m.column(2).xyz().norm();
+
+    let rotation = Matrix3::new(
+        m[(0, 0)] / sx, m[(0, 1)] / sy, m[(0, 2)] / sz,
+        m[(1, 0)] / sx, m[(1, 1)] / sy, m[(1, 2)] / sz,
+        m[(2, 0)] / sx, m[(2, 1)] / sy, m[(2, 2)] / sz,
+    );
+
+    transform.set_translation(translation);
+    transform.set_rotation(UnitQuaternion::from_matrix(&rotation));
+    transform.set_scale(Vector3::new(sx, sy, sz));
+}
+
+/// Load `data` into `Mesh`/`Material`/`Texture` handles and build an entity at
+/// the composed world transform.
+fn spawn_primitive(world: &mut World, world_matrix: &Matrix4<f32>, data: PrimitiveData) {
+    let mat_defaults = world.read_resource::<MaterialDefaults>().0.clone();
+    let bounds = bounding_sphere(&data.positions, max_scale(world_matrix));
+
+    let mesh = world.exec(|loader: AssetLoaderSystemData<'_, Mesh>| {
+        let builder = MeshBuilder::new()
+            .with_vertices(data.positions)
+            .with_vertices(data.normals)
+            .with_vertices(data.tangents)
+            .with_vertices(data.tex_coords)
+            .with_indices(data.indices);
+        loader.load_from_data(builder.into(), ())
+    });
+
+    let albedo = world.exec(|loader: AssetLoaderSystemData<'_, Texture>| {
+        loader.load_from_data(load_from_linear_rgba(data.base_color).into(), ())
+    });
+
+    let material = world.exec(|loader: AssetLoaderSystemData<'_, Material>| {
+        loader.load_from_data(
+            Material {
+                albedo,
+                ..mat_defaults.clone()
+            },
+            (),
+        )
+    });
+
+    let mut transform = Transform::default();
+    apply_world_transform(&mut transform, world_matrix);
+
+    world
+        .create_entity()
+        .with(transform)
+        .with(mesh)
+        .with(material)
+        .with(bounds)
+        .build();
+}
+
+/// Largest of a world matrix's decomposed scale components, used to scale a
+/// local-space radius into world space.
+fn max_scale(m: &Matrix4<f32>) -> f32 {
+    let sx = m.column(0).xyz().norm();
+    let sy = m.column(1).xyz().norm();
+    let sz = m.column(2).xyz().norm();
+    sx.max(sy).max(sz)
+}
+
+/// Bounding sphere of a primitive's positions, with its radius scaled into
+/// world space so the picking and culling systems test against it correctly.
+fn bounding_sphere(positions: &[Position], scale: f32) -> BoundingSphere {
+    if positions.is_empty() {
+        return BoundingSphere::new(Point3::origin(), 0.0);
+    }
+
+    let mut center = Vector3::zeros();
+    for p in positions {
+        center += Vector3::from(p.0);
+    }
+    center /= positions.len() as f32;
+
+    let radius = scale
+        * positions
+            .iter()
+            .map(|p| (Vector3::from(p.0) - center).norm())
+            .fold(0.0f32, f32::max);
+
+    BoundingSphere::new(Point3::from(center), radius)
+}
@@ -1,6 +1,13 @@
 //! Displays spheres with physically based materials.
+mod debug_overlay;
+mod gltf_scene;
+mod physics;
+mod picking;
+mod terrain;
+
 use amethyst::{
     assets::AssetLoaderSystemData,
+    input::{InputBundle, StringBindings},
     ecs::{Join, Read, ReadStorage, System, WriteStorage, Component, DenseVecStorage},
     core::{
         ecs::{Builder, WorldExt},
@@ -9,27 +16,44 @@ use amethyst::{
     },
     renderer::{
         camera::Camera,
-        light::{Light, PointLight},
+        light::{DirectionalLight, Light, PointLight, SpotLight},
         mtl::{Material, MaterialDefaults},
         palette::{LinSrgba, Srgb},
-        plugins::{RenderPbr3D, RenderToWindow},
+        debug_drawing::{DebugLines, DebugLinesParams},
+        plugins::{RenderDebugLines, RenderPbr3D, RenderToWindow},
         rendy::{
             mesh::{Normal, Position, Tangent, TexCoord},
             texture::palette::load_from_linear_rgba,
         },
         shape::Shape,
         types::DefaultBackend,
+        visibility::BoundingSphere,
         Mesh, RenderingBundle, Texture,
     },
-    utils::application_root_dir,
+    utils::{application_root_dir, fps_counter::FpsCounterBundle},
     window::ScreenDimensions,
     Application, GameData, GameDataBuilder, SimpleState, StateData,
 };
-use nalgebra::Vector3;
+use nalgebra::{Unit, Vector3};
+
+/// Authored scene to import on start, relative to the assets directory.
+const SCENE_PATH: &str = "scene.gltf";
+
+/// Seed and vertex-grid resolution of the generated terrain.
+const TERRAIN_SEED: u32 = 0;
+const TERRAIN_SIZE: usize = 256;
+
+/// Selects the render-graph layer at startup: the debug-line overlay is enabled
+/// whenever the `AMETHYST_DEBUG` environment variable is set.
+fn render_with_debug() -> bool {
+    std::env::var_os("AMETHYST_DEBUG").is_some()
+}
 
 pub enum LightColorEnum {
     Red,
     Green,
+    Directional,
+    Spot,
     None, // hack: probably a better way to do this in Amethyst
 }
 
@@ -53,68 +77,153 @@ struct Example {}
 impl SimpleState for Example {
     fn on_start(&mut self, data: StateData<'_, GameData<'_, '_>>) {
         let StateData { world, .. } = data;
-        let mat_defaults = world.read_resource::<MaterialDefaults>().0.clone();
 
-        println!("Load mesh");
-        let (mesh, albedo) = {
-            let mesh = world.exec(|loader: AssetLoaderSystemData<'_, Mesh>| {
-                loader.load_from_data(
-                    Shape::Cone(7)
-                        .generate::<(Vec<Position>, Vec<Normal>, Vec<Tangent>, Vec<TexCoord>)>(None)
-                        .into(),
+        println!("Generate terrain");
+        let terrain = terrain::gen_terrain_mesh(TERRAIN_SEED, TERRAIN_SIZE);
+        let radius = terrain.radius;
+        let terrain_mesh = world.exec(|loader: AssetLoaderSystemData<'_, Mesh>| {
+            loader.load_from_data(terrain.mesh.into(), ())
+        });
+        let mat_defaults = world.read_resource::<MaterialDefaults>().0.clone();
+        let terrain_mtl = world.exec(
+            |(mtl_loader, tex_loader): (
+                AssetLoaderSystemData<'_, Material>,
+                AssetLoaderSystemData<'_, Texture>,
+            )| {
+                let albedo = tex_loader.load_from_data(
+                    load_from_linear_rgba(LinSrgba::new(0.2, 0.45, 0.15, 1.0)).into(),
                     (),
-                )
-            });
-            let albedo = world.exec(|loader: AssetLoaderSystemData<'_, Texture>| {
-                loader.load_from_data(
-                    load_from_linear_rgba(LinSrgba::new(1.0, 1.0, 1.0, 0.5)).into(),
+                );
+                mtl_loader.load_from_data(
+                    Material {
+                        albedo,
+                        ..mat_defaults.clone()
+                    },
                     (),
                 )
-            });
+            },
+        );
+        world
+            .create_entity()
+            .with(Transform::default())
+            .with(terrain_mesh)
+            .with(terrain_mtl)
+            .with(BoundingSphere::new(nalgebra::Point3::origin(), radius))
+            .build();
 
-            (mesh, albedo)
-        };
+        println!("Load glTF scene");
+        let scene_path = application_root_dir()
+            .expect("failed to locate application root")
+            .join("assets/")
+            .join(SCENE_PATH);
+        gltf_scene::load_scene(world, &scene_path.to_string_lossy());
+
+        println!("Build highlight material");
+        let highlight = world.exec(
+            |(mtl_loader, tex_loader): (
+                AssetLoaderSystemData<'_, Material>,
+                AssetLoaderSystemData<'_, Texture>,
+            )| {
+                let albedo = tex_loader.load_from_data(
+                    load_from_linear_rgba(LinSrgba::new(1.0, 0.8, 0.0, 1.0)).into(),
+                    (),
+                );
+                let metallic_roughness = tex_loader.load_from_data(
+                    load_from_linear_rgba(LinSrgba::new(0.0, 0.2, 0.9, 0.0)).into(),
+                    (),
+                );
+                mtl_loader.load_from_data(
+                    Material {
+                        albedo,
+                        metallic_roughness,
+                        ..mat_defaults.clone()
+                    },
+                    (),
+                )
+            },
+        );
+        world.insert(picking::Selected::with_highlight(highlight));
 
-        println!("Create shapes");
-        let n = 201;
-        for i in 0..n {
-            for j in 0..n {
-                let roughness = 0.0;
-                let metallic = 0.0;
-
-                let mut pos = Transform::default();
-                pos.set_translation_xyz(2.5f32 * (i - n/2) as f32, 2.5f32 * (j - n/2) as f32, 0.0);
-                pos.set_rotation_x_axis(std::f32::consts::PI);
-
-                let mtl = world.exec(
-                    |(mtl_loader, tex_loader): (
-                        AssetLoaderSystemData<'_, Material>,
-                        AssetLoaderSystemData<'_, Texture>,
-                    )| {
-                        let metallic_roughness = tex_loader.load_from_data(
-                            load_from_linear_rgba(LinSrgba::new(0.0, roughness, metallic, 0.0))
-                                .into(),
-                            (),
-                        );
-
-                        mtl_loader.load_from_data(
-                            Material {
-                                albedo: albedo.clone(),
-                                metallic_roughness,
-                                ..mat_defaults.clone()
-                            },
-                            (),
-                        )
+        println!("Configure physics emitter");
+        let sphere_mesh = world.exec(|loader: AssetLoaderSystemData<'_, Mesh>| {
+            loader.load_from_data(
+                Shape::Sphere(16, 16)
+                    .generate::<(Vec<Position>, Vec<Normal>, Vec<Tangent>, Vec<TexCoord>)>(None)
+                    .into(),
+                (),
+            )
+        });
+        let sphere_mtl = world.exec(
+            |(mtl_loader, tex_loader): (
+                AssetLoaderSystemData<'_, Material>,
+                AssetLoaderSystemData<'_, Texture>,
+            )| {
+                let albedo = tex_loader.load_from_data(
+                    load_from_linear_rgba(LinSrgba::new(0.8, 0.2, 0.2, 1.0)).into(),
+                    (),
+                );
+                mtl_loader.load_from_data(
+                    Material {
+                        albedo,
+                        ..mat_defaults.clone()
                     },
+                    (),
+                )
+            },
+        );
+        // Flat arena floor at y = 0 that the dynamic bodies collide with.
+        let ground_mesh = world.exec(|loader: AssetLoaderSystemData<'_, Mesh>| {
+            loader.load_from_data(
+                Shape::Plane(None)
+                    .generate::<(Vec<Position>, Vec<Normal>, Vec<Tangent>, Vec<TexCoord>)>(Some((
+                        physics::ARENA_HALF_EXTENT,
+                        physics::ARENA_HALF_EXTENT,
+                        1.0,
+                    )))
+                    .into(),
+                (),
+            )
+        });
+        let ground_mtl = world.exec(
+            |(mtl_loader, tex_loader): (
+                AssetLoaderSystemData<'_, Material>,
+                AssetLoaderSystemData<'_, Texture>,
+            )| {
+                let albedo = tex_loader.load_from_data(
+                    load_from_linear_rgba(LinSrgba::new(0.25, 0.25, 0.28, 1.0)).into(),
+                    (),
                 );
+                mtl_loader.load_from_data(
+                    Material {
+                        albedo,
+                        ..mat_defaults.clone()
+                    },
+                    (),
+                )
+            },
+        );
+        let mut ground_transform = Transform::default();
+        ground_transform.set_rotation_x_axis(-std::f32::consts::FRAC_PI_2);
+        world
+            .create_entity()
+            .with(ground_transform)
+            .with(ground_mesh)
+            .with(ground_mtl)
+            .build();
 
-                world
-                    .create_entity()
-                    .with(pos)
-                    .with(mesh.clone())
-                    .with(mtl)
-                    .build();
-            }
+        world.insert(physics::Emitter {
+            origin: Vector3::new(0.0, 40.0, 0.0),
+            interval: 0.5,
+            accumulator: 0.0,
+            max: 64,
+            spawned: 0,
+            mesh: sphere_mesh,
+            material: sphere_mtl,
+        });
+
+        if render_with_debug() {
+            world.insert(DebugLines::new());
+            world.insert(DebugLinesParams { line_width: 2.0 });
         }
 
         println!("Create lights");
@@ -152,6 +261,40 @@ impl SimpleState for Example {
             .with(LightColor{color: LightColorEnum::Green })
             .build();
 
+        let sun: Light = DirectionalLight {
+            color: Srgb::new(1.0, 0.95, 0.8),
+            intensity: 2.0,
+            direction: Unit::new_normalize(Vector3::new(-0.3, -1.0, -0.2)),
+        }
+            .into();
+
+        world
+            .create_entity()
+            .with(sun)
+            .with(Transform::default())
+            .with(LightColor{color: LightColorEnum::Directional})
+            .build();
+
+        let spot: Light = SpotLight {
+            angle: std::f32::consts::FRAC_PI_6,
+            color: Srgb::new(0.4, 0.7, 1.0),
+            direction: Vector3::new(0.0, -1.0, 0.0),
+            intensity: 40.0,
+            range: 60.0,
+            smoothness: 4.0,
+        }
+            .into();
+
+        let mut spot_transform = Transform::default();
+        spot_transform.set_translation_xyz(0.0, 30.0, 0.0);
+
+        world
+            .create_entity()
+            .with(spot)
+            .with(spot_transform)
+            .with(LightColor{color: LightColorEnum::Spot})
+            .build();
+
         println!("Put camera");
 
         let mut transform = Transform::default();
@@ -176,21 +319,46 @@ pub struct MoveLightsSystem;
 impl<'s> System<'s> for MoveLightsSystem {
     type SystemData = (
         WriteStorage<'s, Transform>,
-        ReadStorage<'s, Light>,
+        WriteStorage<'s, Light>,
         Read<'s, Time>,
         ReadStorage<'s, LightColor>
     );
 
-    fn run(&mut self, (mut transforms, lights, time, light_colors): Self::SystemData) {
-        for (light_color, transform) in (&light_colors, &mut transforms).join() {
-            let seconds = time.absolute_real_time_seconds() as f32;
-            let movement_y = -(seconds*10.0).sin()*100.0;
-            let movement_x = (seconds*10.0).cos()*100.0;
+    fn run(&mut self, (mut transforms, mut lights, time, light_colors): Self::SystemData) {
+        let seconds = time.absolute_real_time_seconds() as f32;
+        let movement_y = -(seconds*10.0).sin()*100.0;
+        let movement_x = (seconds*10.0).cos()*100.0;
+
+        for (light_color, light, transform) in (&light_colors, &mut lights, &mut transforms).join() {
             match light_color.color {
-                LightColorEnum::Red => transform.set_translation_xyz(movement_x, movement_y, -3.0),
-                LightColorEnum::Green => transform.set_translation_xyz(movement_y, movement_x, -3.0),
-                _ => transform,
-            };
+                // Point lights sweep a circle by translating their transform.
+                LightColorEnum::Red => {
+                    transform.set_translation_xyz(movement_x, movement_y, -3.0);
+                }
+                LightColorEnum::Green => {
+                    transform.set_translation_xyz(movement_y, movement_x, -3.0);
+                }
+                // The sun rotates its direction rather than its position.
+                LightColorEnum::Directional => {
+                    if let Light::Directional(dir) = light {
+                        dir.direction = Unit::new_normalize(Vector3::new(
+                            seconds.cos(),
+                            -1.0,
+                            seconds.sin(),
+                        ));
+                    }
+                }
+                // The spot light orbits and re-aims its cone at the origin.
+                LightColorEnum::Spot => {
+                    let pos = Vector3::new(30.0 * seconds.cos(), 30.0, 30.0 * seconds.sin());
+                    transform.set_translation(pos);
+                    transform.face_towards(Vector3::new(0.0, 0.0, 0.0), Vector3::new(0.0, 1.0, 0.0));
+                    if let Light::Spot(spot) = light {
+                        spot.direction = (-pos).normalize();
+                    }
+                }
+                LightColorEnum::None => {}
+            }
         }
     }
 }
@@ -220,18 +388,46 @@ fn main() -> amethyst::Result<()> {
     let display_config_path = app_root.join("config/display.ron");
     let assets_dir = app_root.join("assets/");
 
-    let game_data = GameDataBuilder::default()
+    let debug = render_with_debug();
+
+    // Stack the PBR pass, and layer the debug-line plugin on top when enabled.
+    let mut rendering = RenderingBundle::<DefaultBackend>::new()
+        .with_plugin(
+            RenderToWindow::from_config_path(display_config_path)
+                .with_clear([0.34, 0.36, 0.52, 1.0]),
+        )
+        .with_plugin(RenderPbr3D::default());
+    if debug {
+        rendering = rendering.with_plugin(RenderDebugLines::default());
+    }
+
+    let mut game_data = GameDataBuilder::default()
         .with_bundle(TransformBundle::new())?
-        .with_bundle(
-            RenderingBundle::<DefaultBackend>::new()
-                .with_plugin(
-                    RenderToWindow::from_config_path(display_config_path)
-                        .with_clear([0.34, 0.36, 0.52, 1.0]),
-                )
-                .with_plugin(RenderPbr3D::default()),
-        )?
+        .with_bundle(InputBundle::<StringBindings>::new())?
+        .with_bundle(FpsCounterBundle::default())?
+        .with_bundle(rendering)?
         .with(MoveLightsSystem, "move_lights_system", &[])
-        .with(MoveCameraSystem, "move_camera_system", &[]);
+        .with(MoveCameraSystem, "move_camera_system", &[])
+        .with(
+            picking::PickingSystem::default(),
+            "picking_system",
+            &["input_system"],
+        )
+        .with(physics::EmitterSystem, "emitter_system", &[])
+        .with(
+            physics::PhysicsStepSystem,
+            "physics_step_system",
+            &["emitter_system"],
+        )
+        .with(debug_overlay::FpsDisplaySystem, "fps_display_system", &[]);
+
+    if debug {
+        game_data = game_data.with(
+            debug_overlay::DebugDrawSystem,
+            "debug_draw_system",
+            &["move_lights_system"],
+        );
+    }
 
     let mut game = Application::new(assets_dir, Example::default(), game_data)?;
     game.run();
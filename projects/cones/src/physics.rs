@@ -0,0 +1,163 @@
+//! A tiny rigid-body integrator with a shape-spawning emitter.
+use amethyst::{
+    assets::Handle,
+    core::{
+        ecs::{
+            Component, DenseVecStorage, Entities, Join, LazyUpdate, Read, System, WriteExpect,
+            WriteStorage,
+        },
+        math::{Point3, Vector3},
+        timing::Time,
+        Transform,
+    },
+    renderer::{mtl::Material, visibility::BoundingSphere, Mesh},
+};
+use rand::Rng;
+
+/// Downward acceleration applied to every dynamic body each step.
+const GRAVITY: f32 = -9.81;
+/// Fraction of normal velocity retained when a body bounces off the ground.
+const RESTITUTION: f32 = 0.6;
+/// Height of the collision plane the bodies rest on.
+const GROUND_Y: f32 = 0.0;
+/// Half-width of the square arena; bodies bounce off its four walls.
+pub const ARENA_HALF_EXTENT: f32 = 40.0;
+
+/// Primitive collision shape carried by a dynamic body.
+pub enum Collider {
+    Sphere { radius: f32 },
+}
+
+impl Collider {
+    /// Distance from the body centre to its lowest point along `y`.
+    fn bottom_offset(&self) -> f32 {
+        match self {
+            Collider::Sphere { radius } => *radius,
+        }
+    }
+}
+
+impl Component for Collider {
+    type Storage = DenseVecStorage<Self>;
+}
+
+/// Simulated body pose: linear velocity and mass.
+pub struct RigidBody {
+    pub velocity: Vector3<f32>,
+    pub mass: f32,
+}
+
+impl Component for RigidBody {
+    type Storage = DenseVecStorage<Self>;
+}
+
+/// Spawns new dynamic shapes at a fixed interval from `origin`, up to `max`
+/// bodies total so the entity count stays bounded.
+pub struct Emitter {
+    pub origin: Vector3<f32>,
+    pub interval: f32,
+    pub accumulator: f32,
+    pub max: u32,
+    pub spawned: u32,
+    pub mesh: Handle<Mesh>,
+    pub material: Handle<Material>,
+}
+
+/// Integrates gravity and velocities each frame, updating transforms from the
+/// simulated pose and bouncing bodies off the ground plane.
+pub struct PhysicsStepSystem;
+
+impl<'s> System<'s> for PhysicsStepSystem {
+    type SystemData = (
+        WriteStorage<'s, Transform>,
+        WriteStorage<'s, RigidBody>,
+        WriteStorage<'s, Collider>,
+        Read<'s, Time>,
+    );
+
+    fn run(&mut self, (mut transforms, mut bodies, mut colliders, time): Self::SystemData) {
+        let dt = time.delta_seconds();
+
+        for (transform, body, collider) in (&mut transforms, &mut bodies, &mut colliders).join() {
+            body.velocity.y += GRAVITY * dt;
+
+            let mut translation = *transform.translation() + body.velocity * dt;
+
+            // Resolve penetration of the ground plane and bounce.
+            let floor = GROUND_Y + collider.bottom_offset();
+            if translation.y < floor {
+                translation.y = floor;
+                if body.velocity.y < 0.0 {
+                    body.velocity.y = -body.velocity.y * RESTITUTION;
+                }
+            }
+
+            // Keep bodies inside the arena by bouncing off the side walls.
+            let wall = ARENA_HALF_EXTENT - collider.bottom_offset();
+            if translation.x.abs() > wall {
+                translation.x = translation.x.signum() * wall;
+                body.velocity.x = -body.velocity.x * RESTITUTION;
+            }
+            if translation.z.abs() > wall {
+                translation.z = translation.z.signum() * wall;
+                body.velocity.z = -body.velocity.z * RESTITUTION;
+            }
+
+            transform.set_translation(translation);
+        }
+    }
+}
+
+/// Spawns dynamic spheres from the `Emitter` resource with randomized initial
+/// velocity.
+pub struct EmitterSystem;
+
+impl<'s> System<'s> for EmitterSystem {
+    type SystemData = (
+        Entities<'s>,
+        WriteExpect<'s, Emitter>,
+        Read<'s, Time>,
+        Read<'s, LazyUpdate>,
+    );
+
+    fn run(&mut self, (entities, mut emitter, time, lazy): Self::SystemData) {
+        let dt = time.delta_seconds();
+        let mut rng = rand::thread_rng();
+
+        {
+            let emitter = &mut *emitter;
+            if emitter.spawned >= emitter.max {
+                return;
+            }
+
+            emitter.accumulator += dt;
+            if emitter.accumulator < emitter.interval {
+                return;
+            }
+            emitter.accumulator -= emitter.interval;
+            emitter.spawned += 1;
+
+            let radius = rng.gen_range(0.5..1.5);
+            let velocity = Vector3::new(
+                rng.gen_range(-6.0..6.0),
+                rng.gen_range(2.0..8.0),
+                rng.gen_range(-6.0..6.0),
+            );
+
+            let mut transform = Transform::default();
+            transform.set_translation(emitter.origin);
+
+            lazy.create_entity(&entities)
+                .with(transform)
+                .with(emitter.mesh.clone())
+                .with(emitter.material.clone())
+                .with(Collider::Sphere { radius })
+                .with(BoundingSphere::new(Point3::origin(), radius))
+                .with(RigidBody {
+                    velocity,
+                    mass: radius * radius * radius,
+                })
+                .build();
+        }
+    }
+}
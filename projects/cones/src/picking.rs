@@ -0,0 +1,177 @@
+//! Casts a ray from the camera through the cursor and selects the entity under it.
+use amethyst::{
+    assets::Handle,
+    core::{
+        ecs::{Entities, Entity, Join, Read, ReadStorage, System, Write, WriteStorage},
+        math::{Point3, Vector3, Vector4},
+        Transform,
+    },
+    input::{InputHandler, StringBindings},
+    renderer::{camera::Camera, mtl::Material, visibility::BoundingSphere},
+    window::ScreenDimensions,
+    winit::MouseButton,
+};
+
+/// Highlight material swapped in for the selected entity, and the entity's
+/// original material so it can be restored on deselect.
+#[derive(Default)]
+pub struct Selected {
+    pub highlight: Option<Handle<Material>>,
+    entity: Option<Entity>,
+    original: Option<Handle<Material>>,
+}
+
+impl Selected {
+    /// Create the selection resource carrying the highlight material used when
+    /// an entity is picked.
+    pub fn with_highlight(highlight: Handle<Material>) -> Self {
+        Self {
+            highlight: Some(highlight),
+            entity: None,
+            original: None,
+        }
+    }
+}
+
+/// A ray in world space: origin and normalized direction.
+struct Ray {
+    origin: Point3<f32>,
+    direction: Vector3<f32>,
+}
+
+/// Selects the nearest entity under the cursor on a left-click, highlighting
+/// its material and reverting the previously selected one.
+pub struct PickingSystem {
+    was_down: bool,
+}
+
+impl Default for PickingSystem {
+    fn default() -> Self {
+        Self { was_down: false }
+    }
+}
+
+impl<'s> System<'s> for PickingSystem {
+    type SystemData = (
+        Entities<'s>,
+        ReadStorage<'s, Camera>,
+        ReadStorage<'s, Transform>,
+        ReadStorage<'s, BoundingSphere>,
+        WriteStorage<'s, Handle<Material>>,
+        Read<'s, InputHandler<StringBindings>>,
+        Read<'s, ScreenDimensions>,
+        Write<'s, Selected>,
+    );
+
+    fn run(
+        &mut self,
+        (entities, cameras, transforms, spheres, mut materials, input, screen, mut selected): Self::SystemData,
+    ) {
+        // Only act on the press edge of the left mouse button.
+        let is_down = input.mouse_button_is_down(MouseButton::Left);
+        let clicked = is_down && !self.was_down;
+        self.was_down = is_down;
+        if !clicked {
+            return;
+        }
+
+        let (cursor_x, cursor_y) = match input.mouse_position() {
+            Some(pos) => pos,
+            None => return,
+        };
+
+        let ray = match (&cameras, &transforms).join().next() {
+            Some((camera, cam_transform)) => {
+                match build_ray(camera, cam_transform, cursor_x, cursor_y, &screen) {
+                    Some(ray) => ray,
+                    None => return,
+                }
+            }
+            None => return,
+        };
+
+        // Nearest positive ray-sphere hit across all bounded entities.
+        let mut best: Option<(Entity, f32)> = None;
+        for (entity, transform, sphere) in (&entities, &transforms, &spheres).join() {
+            let center = transform.global_matrix().transform_point(&sphere.center);
+            if let Some(t) = ray_sphere_intersection(&ray, &center, sphere.radius) {
+                if best.map_or(true, |(_, best_t)| t < best_t) {
+                    best = Some((entity, t));
+                }
+            }
+        }
+
+        // Revert the previously selected entity.
+        if let (Some(prev), Some(original)) = (selected.entity, selected.original.clone()) {
+            materials.insert(prev, original).ok();
+        }
+        selected.entity = None;
+        selected.original = None;
+
+        if let (Some((entity, _)), Some(highlight)) = (best, selected.highlight.clone()) {
+            if let Some(original) = materials.get(entity).cloned() {
+                selected.original = Some(original);
+                selected.entity = Some(entity);
+                materials.insert(entity, highlight).ok();
+            }
+        }
+    }
+}
+
+/// Unproject the cursor's NDC coordinates through the camera to a world-space
+/// ray, using the near and far points on the pick frustum.
+fn build_ray(
+    camera: &Camera,
+    cam_transform: &Transform,
+    cursor_x: f32,
+    cursor_y: f32,
+    screen: &ScreenDimensions,
+) -> Option<Ray> {
+    let ndc_x = 2.0 * cursor_x / screen.width() - 1.0;
+    let ndc_y = 1.0 - 2.0 * cursor_y / screen.height();
+
+    // A valid projection is always invertible; bail out rather than fabricate
+    // a bogus ray if it somehow is not.
+    let inv_proj = camera.as_matrix().try_inverse()?;
+    let view = cam_transform.global_matrix();
+
+    let unproject = |ndc_z: f32| -> Point3<f32> {
+        let clip = Vector4::new(ndc_x, ndc_y, ndc_z, 1.0);
+        let eye = inv_proj * clip;
+        let eye = eye / eye.w;
+        let world = view * eye;
+        Point3::new(world.x, world.y, world.z)
+    };
+
+    // rendy uses a [0, 1] depth range, so the near plane is at NDC z = 0.
+    let near = unproject(0.0);
+    let far = unproject(1.0);
+    let direction = (far - near).normalize();
+
+    Some(Ray {
+        origin: near,
+        direction,
+    })
+}
+
+/// Smallest positive `t` solving `|o + t·d − c|² = r²`, or `None` if the ray
+/// misses the sphere.
+fn ray_sphere_intersection(ray: &Ray, center: &Point3<f32>, radius: f32) -> Option<f32> {
+    let oc = ray.origin - center;
+    let a = ray.direction.dot(&ray.direction);
+    let b = 2.0 * oc.dot(&ray.direction);
+    let c = oc.dot(&oc) - radius * radius;
+    let discriminant = b * b - 4.0 * a * c;
+
+    if discriminant < 0.0 {
+        return None;
+    }
+
+    let sqrt_d = discriminant.sqrt();
+    let t0 = (-b - sqrt_d) / (2.0 * a);
+    let t1 = (-b + sqrt_d) / (2.0 * a);
+
+    [t0, t1].iter().cloned().filter(|t| *t > 0.0).fold(None, |acc, t| {
+        Some(acc.map_or(t, |best: f32| best.min(t)))
+    })
+}
@@ -0,0 +1,123 @@
+//! Generates a heightmap terrain mesh from fractal Brownian motion noise.
+use amethyst::{
+    core::math::Vector3,
+    renderer::rendy::mesh::{MeshBuilder, Normal, Position, Tangent, TexCoord},
+};
+use noise::{NoiseFn, OpenSimplex, Seedable};
+
+/// World-space extent of a single quad in the vertex grid.
+const CELL_SIZE: f32 = 1.0;
+/// Scale applied to the accumulated noise value to give a vertex's height.
+const HEIGHT_SCALE: f32 = 24.0;
+/// Base sampling frequency of the first FBM octave.
+const BASE_FREQUENCY: f64 = 0.02;
+const OCTAVES: u32 = 5;
+const LACUNARITY: f64 = 2.0;
+const GAIN: f32 = 0.5;
+
+/// A generated mesh plus the radius of a bounding sphere centred on its origin,
+/// ready to hand to `AssetLoaderSystemData<Mesh>` and attach for culling.
+pub struct Terrain {
+    pub mesh: MeshBuilder<'static>,
+    pub radius: f32,
+}
+
+/// Build a `size`×`size` vertex grid whose heights come from summing `OCTAVES`
+/// of simplex noise seeded by `seed`, emitting two triangles per quad.
+pub fn gen_terrain_mesh(seed: u32, size: usize) -> Terrain {
+    let simplex = OpenSimplex::new().set_seed(seed);
+
+    let mut positions: Vec<Position> = Vec::with_capacity(size * size);
+    let mut tex_coords: Vec<TexCoord> = Vec::with_capacity(size * size);
+    let half = size as f32 * CELL_SIZE * 0.5;
+    let mut max_height = 0.0f32;
+
+    for z in 0..size {
+        for x in 0..size {
+            let wx = x as f32 * CELL_SIZE - half;
+            let wz = z as f32 * CELL_SIZE - half;
+            let height = fbm(&simplex, wx, wz);
+            max_height = max_height.max(height.abs());
+
+            positions.push(Position([wx, height, wz]));
+            tex_coords.push(TexCoord([
+                x as f32 / (size - 1) as f32,
+                z as f32 / (size - 1) as f32,
+            ]));
+        }
+    }
+
+    // Two triangles per quad, wound counter-clockwise.
+    let mut indices: Vec<u32> = Vec::with_capacity((size - 1) * (size - 1) * 6);
+    for z in 0..size - 1 {
+        for x in 0..size - 1 {
+            let i = (z * size + x) as u32;
+            let right = i + 1;
+            let down = i + size as u32;
+            let down_right = down + 1;
+
+            indices.extend_from_slice(&[i, down, right]);
+            indices.extend_from_slice(&[right, down, down_right]);
+        }
+    }
+
+    let normals = compute_normals(&positions, &indices);
+    // `RenderPbr3D` binds the `pos_norm_tang_tex` format, so a tangent buffer
+    // is required even though the terrain uses one constant tangent throughout.
+    let tangents = vec![Tangent([1.0, 0.0, 0.0, 1.0]); positions.len()];
+
+    let mesh = MeshBuilder::new()
+        .with_vertices(positions)
+        .with_vertices(normals)
+        .with_vertices(tangents)
+        .with_vertices(tex_coords)
+        .with_indices(indices);
+
+    // A sphere spanning the horizontal footprint and the tallest peak.
+    let radius = (half * half * 2.0 + max_height * max_height).sqrt();
+
+    Terrain { mesh, radius }
+}
+
+/// Sample fractal Brownian motion at `(x, z)`: accumulate octaves, scaling
+/// frequency by `LACUNARITY` and amplitude by `GAIN` each step.
+fn fbm(simplex: &OpenSimplex, x: f32, z: f32) -> f32 {
+    let mut frequency = BASE_FREQUENCY;
+    let mut amplitude = 1.0f32;
+    let mut value = 0.0f32;
+
+    for _ in 0..OCTAVES {
+        let sample = simplex.get([x as f64 * frequency, z as f64 * frequency]) as f32;
+        value += sample * amplitude;
+        frequency *= LACUNARITY;
+        amplitude *= GAIN;
+    }
+
+    value * HEIGHT_SCALE
+}
+
+/// Per-vertex normals, averaged from the face normals of every triangle that
+/// touches each vertex.
+fn compute_normals(positions: &[Position], indices: &[u32]) -> Vec<Normal> {
+    let mut accum = vec![Vector3::zeros(); positions.len()];
+
+    for tri in indices.chunks_exact(3) {
+        let (a, b, c) = (tri[0] as usize, tri[1] as usize, tri[2] as usize);
+        let va = Vector3::from(positions[a].0);
+        let vb = Vector3::from(positions[b].0);
+        let vc = Vector3::from(positions[c].0);
+        let face = (vb - va).cross(&(vc - va));
+
+        accum[a] += face;
+        accum[b] += face;
+        accum[c] += face;
+    }
+
+    accum
+        .into_iter()
+        .map(|n| {
+            let n = n.try_normalize(1.0e-6).unwrap_or_else(|| Vector3::y());
+            Normal([n.x, n.y, n.z])
+        })
+        .collect()
+}